@@ -11,15 +11,28 @@ pub struct CliArgs {
     #[arg(long = "help", action = clap::ArgAction::Help)]
     pub help: Option<bool>,
     /// Pattern to search for
-    pub pattern: String,
+    #[arg(required_unless_present_any = ["regexp", "pattern_file"])]
+    pub pattern: Option<String>,
 
     /// Files or directories to search
     pub files: Vec<PathBuf>,
 
+    /// Use PATTERN as a matching pattern (may be repeated to combine patterns)
+    #[arg(short = 'e', long = "regexp", value_name = "PATTERN")]
+    pub regexp: Vec<String>,
+
     /// Ignore case distinctions in patterns and input data
     #[arg(short = 'i', long = "ignore-case")]
     pub ignore_case: bool,
 
+    /// Match case-insensitively if the pattern is all lowercase, case-sensitively otherwise
+    #[arg(short = 'S', long = "smart-case")]
+    pub smart_case: bool,
+
+    /// Use the PCRE2 regex engine, enabling backreferences and lookaround
+    #[arg(short = 'P', long = "pcre2")]
+    pub pcre2: bool,
+
     /// Treat PATTERNS as fixed strings, not regular expressions
     #[arg(short = 'F', long = "fixed-strings")]
     pub fixed_strings: bool,
@@ -100,6 +113,42 @@ pub struct CliArgs {
     #[arg(long = "include", value_name = "GLOB")]
     pub include: Vec<String>,
 
+    /// Only search files matching the given file type (e.g. `rust`, `py`)
+    #[arg(long = "type", value_name = "NAME")]
+    pub file_type: Vec<String>,
+
+    /// Skip files matching the given file type
+    #[arg(long = "type-not", value_name = "NAME")]
+    pub file_type_not: Vec<String>,
+
+    /// Print the file type definitions (built-in plus any from --type-add) and exit
+    #[arg(long = "type-list")]
+    pub type_list: bool,
+
+    /// Define a custom file type as `name:glob` (may be repeated)
+    #[arg(long = "type-add", value_name = "NAME:GLOB")]
+    pub type_add: Vec<String>,
+
+    /// Transcode file contents from LABEL to UTF-8 before searching (default: auto-detect BOM)
+    #[arg(long = "encoding", value_name = "LABEL")]
+    pub encoding: Option<String>,
+
+    /// Search the decompressed contents of .gz/.bz2/.xz/.zst files
+    #[arg(long = "search-zip")]
+    pub search_zip: bool,
+
+    /// Pipe files through COMMAND and search its stdout instead of the file itself
+    #[arg(long = "pre", value_name = "COMMAND")]
+    pub pre: Option<PathBuf>,
+
+    /// Restrict --pre to files matching GLOB (may be repeated)
+    #[arg(long = "pre-glob", value_name = "GLOB")]
+    pub pre_glob: Vec<String>,
+
+    /// Also run built-in preprocessors by extension (e.g. pdftotext for .pdf) when --pre isn't given
+    #[arg(long = "pre-builtins")]
+    pub pre_builtins: bool,
+
     /// Follow symbolic links
     #[arg(short = 'R', long = "dereference-recursive")]
     pub dereference_recursive: bool,
@@ -108,10 +157,14 @@ pub struct CliArgs {
     #[arg(short = 'a', long = "text")]
     pub text: bool,
 
-    /// Skip binary files
+    /// Skip binary files (detected by a NUL byte in their first few KB, not by extension)
     #[arg(short = 'I', long = "ignore-binary")]
     pub ignore_binary: bool,
 
+    /// Search binary files, replacing NUL bytes so matches can still be reported
+    #[arg(long = "binary")]
+    pub binary: bool,
+
     /// Print byte offset of each match
     #[arg(short = 'b', long = "byte-offset")]
     pub byte_offset: bool,
@@ -131,6 +184,26 @@ pub struct CliArgs {
     /// Use null character as line separator
     #[arg(short = 'z', long = "null")]
     pub null: bool,
+
+    /// Emit results as JSON Lines instead of text
+    #[arg(long = "json")]
+    pub json: bool,
+
+    /// Number of worker threads to use for recursive search (default: number of CPUs)
+    #[arg(long = "threads", value_name = "NUM")]
+    pub threads: Option<usize>,
+
+    /// Don't respect .gitignore, .ignore, or .git/info/exclude while recursing
+    #[arg(long = "no-ignore")]
+    pub no_ignore: bool,
+
+    /// Search hidden files and directories (dotfiles are skipped by default)
+    #[arg(long = "hidden")]
+    pub hidden: bool,
+
+    /// Print a summary of matches, lines, files, and bytes searched after the results
+    #[arg(long = "stats")]
+    pub stats: bool,
 }
 
 #[derive(Clone, Debug, ValueEnum)]
@@ -0,0 +1,89 @@
+//! Transparent decompression (`--search-zip`) and non-UTF-8 encoding support
+//! (`--encoding`) for `search_file`. Both adapters sit at the point where file bytes are
+//! read, before line splitting, so the rest of the search pipeline keeps operating on
+//! plain UTF-8 text.
+
+use encoding_rs::Encoding;
+use std::borrow::Cow;
+use std::io::{self, Read};
+use std::path::Path;
+
+const COMPRESSED_EXTENSIONS: &[&str] = &["gz", "bz2", "xz", "zst", "lz4"];
+
+/// Whether `path`'s extension names one of the compressed formats `decompress` understands.
+pub fn is_compressed(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|ext| COMPRESSED_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Returns the decompressed bytes of `path` if its extension marks it as a supported
+/// compressed format, otherwise `None` (the caller should read it as-is).
+pub fn decompress(path: &Path) -> io::Result<Option<Vec<u8>>> {
+    let ext = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => ext.to_ascii_lowercase(),
+        None => return Ok(None),
+    };
+
+    let file = std::fs::File::open(path)?;
+    let mut out = Vec::new();
+
+    match ext.as_str() {
+        "gz" => {
+            flate2::read::GzDecoder::new(file).read_to_end(&mut out)?;
+        }
+        "bz2" => {
+            bzip2::read::BzDecoder::new(file).read_to_end(&mut out)?;
+        }
+        "xz" => {
+            xz2::read::XzDecoder::new(file).read_to_end(&mut out)?;
+        }
+        "zst" => {
+            zstd::stream::copy_decode(file, &mut out)?;
+        }
+        "lz4" => {
+            lz4_flex::frame::FrameDecoder::new(file).read_to_end(&mut out)?;
+        }
+        _ => return Ok(None),
+    }
+
+    Ok(Some(out))
+}
+
+/// Whether `transcode` will treat `bytes` as something other than plain UTF-8: either an
+/// explicit `--encoding` label was given, or the bytes start with a recognized BOM (notably
+/// UTF-16, whose every other byte is NUL). Callers use this to skip NUL-based binary detection
+/// before transcoding runs, since that NUL-heavy raw form would otherwise look binary.
+pub fn has_explicit_encoding(label: Option<&str>, bytes: &[u8]) -> bool {
+    label.is_some() || Encoding::for_bom(bytes).is_some()
+}
+
+/// Transcodes `bytes` to UTF-8 using `label` (resolved via `encoding_rs::Encoding::for_label`)
+/// or, when no label is given, auto-detects a UTF-8/UTF-16 BOM and otherwise assumes the
+/// bytes are already UTF-8.
+pub fn transcode<'a>(bytes: &'a [u8], label: Option<&str>) -> io::Result<Cow<'a, str>> {
+    let encoding = match label {
+        Some(label) => Encoding::for_label(label.as_bytes())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("unknown encoding '{}'", label)))?,
+        None => match Encoding::for_bom(bytes) {
+            Some((enc, _bom_len)) => enc,
+            None => {
+                // No BOM and no explicit label: assume UTF-8, matching the existing
+                // zero-copy fast path used when no `--encoding` flag is given.
+                return std::str::from_utf8(bytes)
+                    .map(Cow::Borrowed)
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "file contains invalid UTF-8"));
+            }
+        },
+    };
+
+    let (decoded, _encoding_used, had_errors) = encoding.decode(bytes);
+    if had_errors {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("failed to decode file as {}", encoding.name()),
+        ));
+    }
+    Ok(decoded)
+}
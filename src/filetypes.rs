@@ -0,0 +1,65 @@
+//! Named file-type definitions for `--type`/`--type-not`/`--type-list`, so users can write
+//! `greprs --type rust TODO` instead of spelling out `--include '*.rs'`.
+
+use std::collections::HashMap;
+
+/// Built-in type name -> glob patterns, kept lexicographically sorted by name so
+/// `--type-list` prints a predictable, reviewable table.
+pub const BUILTIN_TYPES: &[(&str, &[&str])] = &[
+    ("c", &["*.c", "*.h"]),
+    ("cpp", &["*.cc", "*.cpp", "*.cxx", "*.hpp", "*.hh", "*.h"]),
+    ("go", &["*.go"]),
+    ("html", &["*.htm", "*.html"]),
+    ("java", &["*.java"]),
+    ("js", &["*.js", "*.jsx", "*.mjs"]),
+    ("json", &["*.json"]),
+    ("lock", &["*.lock"]),
+    ("md", &["*.md", "*.markdown"]),
+    ("py", &["*.py", "*.pyi"]),
+    ("rust", &["*.rs"]),
+    ("sh", &["*.sh", "*.bash"]),
+    ("toml", &["*.toml"]),
+    ("ts", &["*.ts", "*.tsx"]),
+    ("yaml", &["*.yml", "*.yaml"]),
+];
+
+/// A mutable registry seeded from `BUILTIN_TYPES`, extendable at runtime via `--type-add`.
+pub struct TypeRegistry {
+    types: HashMap<String, Vec<String>>,
+}
+
+impl TypeRegistry {
+    pub fn with_builtins() -> Self {
+        let mut types = HashMap::new();
+        for (name, globs) in BUILTIN_TYPES {
+            types.insert(name.to_string(), globs.iter().map(|g| g.to_string()).collect());
+        }
+        TypeRegistry { types }
+    }
+
+    /// Registers (or extends) a type from a `name:*.ext` definition, as passed to `--type-add`.
+    pub fn add(&mut self, definition: &str) -> Result<(), String> {
+        let (name, glob) = definition
+            .split_once(':')
+            .ok_or_else(|| format!("invalid --type-add definition '{}', expected 'name:glob'", definition))?;
+        self.types
+            .entry(name.to_string())
+            .or_default()
+            .push(glob.to_string());
+        Ok(())
+    }
+
+    pub fn globs_for(&self, name: &str) -> Option<&[String]> {
+        self.types.get(name).map(|v| v.as_slice())
+    }
+
+    /// Prints every registered type definition, sorted by name, for `--type-list`.
+    pub fn print_list(&self) {
+        let mut names: Vec<&String> = self.types.keys().collect();
+        names.sort();
+        for name in names {
+            let globs = &self.types[name];
+            println!("{}: {}", name, globs.join(", "));
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Minimal `.gitignore`-style ignore matching used by the recursive walker in `search.rs`.
+//!
+//! Ignore files are read as we descend into each directory and accumulate: a rule from a
+//! deeper `.gitignore` takes precedence over one from a shallower directory, and within a
+//! single file later patterns override earlier ones. A leading `!` re-includes a path that a
+//! previous rule excluded, a trailing `/` restricts a rule to directories, and a pattern
+//! containing a `/` anywhere but the end (leading, like `/foo`, or interior, like
+//! `src/foo.txt`) anchors it to the directory containing the ignore file rather than matching
+//! at any depth beneath it.
+
+use glob::Pattern;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+struct Rule {
+    glob: Pattern,
+    negate: bool,
+    dir_only: bool,
+}
+
+/// Accumulated ignore rules for the directory currently being walked, ordered from the
+/// repository root down to the current directory so later (deeper) rules are checked last
+/// and therefore win ties.
+#[derive(Clone, Default)]
+pub struct IgnoreStack {
+    rules: Vec<std::sync::Arc<Rule>>,
+}
+
+impl IgnoreStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new stack with the rules from `dir`'s own ignore files appended on top of
+    /// this one, ready to be used while walking `dir`'s children.
+    pub fn push_dir(&self, dir: &Path) -> Self {
+        let mut rules = self.rules.clone();
+        for name in [".gitignore", ".ignore"] {
+            load_rules_into(&dir.join(name), dir, &mut rules);
+        }
+        load_rules_into(&dir.join(".git").join("info").join("exclude"), dir, &mut rules);
+        IgnoreStack { rules }
+    }
+
+    /// Checks `path` against the accumulated rules. Every rule is prefixed with the directory
+    /// of its ignore file, so `path` must be spelled with the same prefix as the directories
+    /// passed to `push_dir` (as `entry.path()` of a walked directory is). The last matching rule
+    /// wins, so we scan in order and remember the most recent verdict.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            if rule.glob.matches_path(path) {
+                ignored = !rule.negate;
+            }
+        }
+        ignored
+    }
+}
+
+fn load_rules_into(ignore_file: &Path, base_dir: &Path, rules: &mut Vec<std::sync::Arc<Rule>>) {
+    let Ok(contents) = fs::read_to_string(ignore_file) else {
+        return;
+    };
+
+    for raw_line in contents.lines() {
+        let line = raw_line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut pattern = line;
+        let negate = if let Some(rest) = pattern.strip_prefix('!') {
+            pattern = rest;
+            true
+        } else {
+            false
+        };
+
+        let dir_only = pattern.ends_with('/');
+        if dir_only {
+            pattern = &pattern[..pattern.len() - 1];
+        }
+
+        // Standard gitignore semantics: a pattern is anchored to `base_dir` if it has a
+        // separator anywhere but the end (leading, like "/foo", or interior, like
+        // "src/foo.txt"); a pattern with no separator (other than the trailing one already
+        // stripped above) matches at any depth beneath `base_dir`.
+        let anchored = pattern.contains('/');
+        let pattern = pattern.trim_start_matches('/');
+
+        let full_pattern = if anchored {
+            base_dir.join(pattern)
+        } else {
+            base_dir.join("**").join(pattern)
+        };
+
+        if let Some(glob) = compile(&full_pattern) {
+            rules.push(std::sync::Arc::new(Rule {
+                glob,
+                negate,
+                dir_only,
+            }));
+        }
+    }
+}
+
+fn compile(pattern: &PathBuf) -> Option<Pattern> {
+    Pattern::new(&pattern.to_string_lossy()).ok()
+}
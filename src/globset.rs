@@ -0,0 +1,155 @@
+//! A compiled matcher for `--include`/`--exclude` globs, replacing the `Vec<glob::Pattern>`
+//! linear scan with three fast paths chosen per-pattern at build time:
+//!
+//! - pure-extension globs (`*.rs`, `*.log`) go into a hash map keyed on the file extension
+//! - literal, wildcard-free globs go into a hash map keyed on the full filename (exact match,
+//!   like `glob::Pattern` on a pattern with no metacharacters)
+//! - anything else (character classes, multiple wildcards, ...) falls back to one combined
+//!   regex alternation, so even the slow path only costs one engine run per file
+//!
+//! This turns `should_process_file`'s O(files × patterns) work into O(files).
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+pub struct GlobSet {
+    ext_index: HashMap<String, Vec<usize>>,
+    literal_index: HashMap<String, Vec<usize>>,
+    fallback_regex: Option<Regex>,
+    fallback_indices: Vec<usize>,
+}
+
+impl GlobSet {
+    /// Compiles `patterns` (glob syntax, e.g. from `--include`/`--exclude`) into a `GlobSet`.
+    /// Invalid patterns are skipped.
+    pub fn build(patterns: &[String]) -> Self {
+        let mut ext_index: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut literal_index: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut fallback_parts = Vec::new();
+        let mut fallback_indices = Vec::new();
+
+        for (index, pattern) in patterns.iter().enumerate() {
+            if let Some(ext) = pure_extension_glob(pattern) {
+                ext_index.entry(ext).or_default().push(index);
+            } else if is_literal(pattern) {
+                literal_index.entry(pattern.clone()).or_default().push(index);
+            } else if let Some(translated) = glob_to_regex(pattern).filter(|t| Regex::new(t).is_ok()) {
+                fallback_parts.push(format!("(?P<p{}>{})", index, translated));
+                fallback_indices.push(index);
+            }
+        }
+
+        let fallback_regex = if fallback_parts.is_empty() {
+            None
+        } else {
+            Regex::new(&fallback_parts.join("|")).ok()
+        };
+
+        GlobSet {
+            ext_index,
+            literal_index,
+            fallback_regex,
+            fallback_indices,
+        }
+    }
+
+    /// Returns indices (into the original `patterns` slice) of patterns that match
+    /// `filename`: every matching extension and literal pattern, but of the patterns on the
+    /// regex fallback path only the first (lowest-index) one that matches, since they share
+    /// one alternation. An empty result means no pattern matched.
+    pub fn matches(&self, filename: &str) -> Vec<usize> {
+        let mut matched = Vec::new();
+
+        if let Some(ext) = Path::new(filename).extension().and_then(|e| e.to_str()) {
+            if let Some(indices) = self.ext_index.get(ext) {
+                matched.extend(indices.iter().copied());
+            }
+        }
+
+        if let Some(indices) = self.literal_index.get(filename) {
+            matched.extend(indices.iter().copied());
+        }
+
+        if let Some(re) = &self.fallback_regex {
+            if let Some(captures) = re.captures(filename) {
+                for &index in &self.fallback_indices {
+                    if captures.name(&format!("p{}", index)).is_some() {
+                        matched.push(index);
+                    }
+                }
+            }
+        }
+
+        matched
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ext_index.is_empty() && self.literal_index.is_empty() && self.fallback_regex.is_none()
+    }
+}
+
+impl Default for GlobSet {
+    fn default() -> Self {
+        GlobSet::build(&[])
+    }
+}
+
+impl std::fmt::Debug for GlobSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GlobSet")
+            .field("ext_index", &self.ext_index.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Recognizes globs of the exact shape `*.ext` with no other wildcard/class syntax and a
+/// single extension component. Multi-dot globs like `*.tar.gz` are deliberately excluded:
+/// `Path::extension()` only ever returns the last component (`gz`), so indexing them here
+/// would make them unmatchable; they fall through to the regex path instead.
+fn pure_extension_glob(pattern: &str) -> Option<String> {
+    let rest = pattern.strip_prefix("*.")?;
+    if rest.chars().any(|c| matches!(c, '*' | '?' | '[' | ']' | '.')) {
+        return None;
+    }
+    Some(rest.to_string())
+}
+
+fn is_literal(pattern: &str) -> bool {
+    !pattern.chars().any(|c| matches!(c, '*' | '?' | '['))
+}
+
+/// Translates a small subset of glob syntax to an equivalent regex, anchored with `^`/`$`
+/// so it must match the whole filename: `*` matches any run of characters, `?` matches one, and bracket classes pass through
+/// verbatim. Used only for patterns that don't fit the extension or literal fast paths.
+fn glob_to_regex(pattern: &str) -> Option<String> {
+    let mut out = String::with_capacity(pattern.len() * 2);
+    out.push('^');
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => out.push_str(".*"),
+            '?' => out.push('.'),
+            '[' => {
+                out.push('[');
+                for next in chars.by_ref() {
+                    out.push(next);
+                    if next == ']' {
+                        break;
+                    }
+                }
+            }
+            c if is_regex_meta_char(c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('$');
+    Some(out)
+}
+
+fn is_regex_meta_char(c: char) -> bool {
+    matches!(c, '.' | '+' | '(' | ')' | '|' | '^' | '$' | '{' | '}' | '\\')
+}
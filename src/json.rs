@@ -0,0 +1,80 @@
+//! JSON Lines event types for `--json` output, modeled on ripgrep's `--json` printer.
+
+use serde::Serialize;
+
+/// Either UTF-8 text or, when the underlying bytes aren't valid UTF-8, a base64-encoded
+/// fallback. Every text-bearing field in the JSON event stream goes through this type so
+/// that non-UTF-8 file contents never break the output format.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Text {
+    Utf8 { text: String },
+    Bytes { bytes: String },
+}
+
+impl Text {
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(s) => Text::Utf8 { text: s.to_string() },
+            Err(_) => Text::Bytes { bytes: base64::encode(bytes) },
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct BeginData {
+    pub path: Text,
+}
+
+#[derive(Serialize)]
+pub struct SubMatch {
+    #[serde(rename = "match")]
+    pub text: Text,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Serialize)]
+pub struct MatchData {
+    pub path: Text,
+    pub lines: Text,
+    pub line_number: Option<u64>,
+    pub absolute_offset: u64,
+    pub submatches: Vec<SubMatch>,
+}
+
+/// A context line (from `-A`/`-B`/`-C`) surrounding a match, reported the same way ripgrep
+/// does: like a match, but without submatch spans since nothing on the line matched.
+#[derive(Serialize)]
+pub struct ContextData {
+    pub path: Text,
+    pub lines: Text,
+    pub line_number: Option<u64>,
+    pub absolute_offset: u64,
+}
+
+#[derive(Serialize)]
+pub struct EndData {
+    pub path: Text,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum JsonEvent {
+    #[serde(rename = "begin")]
+    Begin(BeginData),
+    #[serde(rename = "match")]
+    Match(MatchData),
+    #[serde(rename = "context")]
+    Context(ContextData),
+    #[serde(rename = "end")]
+    End(EndData),
+}
+
+/// Writes a single JSON event followed by a newline, matching the JSON Lines convention.
+pub fn write_event<W: std::io::Write>(writer: &mut W, event: &JsonEvent) -> std::io::Result<()> {
+    let line = serde_json::to_string(event).map_err(|e| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    })?;
+    writeln!(writer, "{}", line)
+}
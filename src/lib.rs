@@ -1,7 +1,17 @@
 pub mod cli;
+pub mod decode;
+pub mod filetypes;
+pub mod gitignore;
+pub mod globset;
+pub mod json;
+pub mod matcher;
+pub mod preprocess;
 pub mod search;
+pub mod stats;
 pub mod utils;
 
 pub use cli::CliArgs;
-pub use search::{SearchConfig, visit_path};
+pub use matcher::Matcher;
+pub use search::{BinaryDetection, SearchConfig, visit_path};
+pub use stats::Stats;
 pub use utils::{build_regex, RegexConfig};
@@ -1,16 +1,43 @@
 use clap::Parser;
 use cli::{CliArgs, ColorOption};
-use utils::{build_regex, RegexConfig};
-use search::{SearchConfig, visit_path};
+use filetypes::TypeRegistry;
+use globset::GlobSet;
+use preprocess::PreprocessorConfig;
+use utils::{build_matcher, combine_alternatives, RegexConfig};
+use search::{BinaryDetection, SearchConfig, search_stdin, visit_path};
+use stats::Stats;
 use std::io::{self, Write, BufWriter};
+use std::sync::Arc;
+use std::time::Instant;
 
 mod cli;
+mod decode;
+mod filetypes;
+mod gitignore;
+mod globset;
+mod json;
+mod matcher;
+mod preprocess;
 mod search;
+mod stats;
 mod utils;
 
 fn main() -> io::Result<()> {
     let args = CliArgs::parse();
-    
+
+    let mut type_registry = TypeRegistry::with_builtins();
+    for definition in &args.type_add {
+        if let Err(e) = type_registry.add(definition) {
+            eprintln!("greprs: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    if args.type_list {
+        type_registry.print_list();
+        return Ok(());
+    }
+
     // Handle context options
     let (before_context, after_context) = match args.context {
         Some(n) => (Some(n), Some(n)),
@@ -24,25 +51,86 @@ fn main() -> io::Result<()> {
         ColorOption::Auto => atty::is(atty::Stream::Stdout),
     };
     
-    // Parse exclude/include patterns with better error handling
-    let exclude_patterns: Vec<_> = args.exclude.iter()
-        .filter_map(|s| glob::Pattern::new(s).ok())
-        .collect();
-    
-    let include_patterns: Vec<_> = args.include.iter()
-        .filter_map(|s| glob::Pattern::new(s).ok())
-        .collect();
-    
+    let exclude_globs = GlobSet::build(&args.exclude);
+    let include_globs = GlobSet::build(&args.include);
+
+    // `--type`/`--type-not` are resolved to their own `GlobSet`s up front and checked
+    // separately in `should_process_file`, rather than folded into `include_globs`/
+    // `exclude_globs`, so a file must satisfy the plain globs *and* the type filters.
+    let mut type_filters = Vec::new();
+    for name in &args.file_type {
+        match type_registry.globs_for(name) {
+            Some(globs) => type_filters.push((GlobSet::build(globs), true)),
+            None => {
+                eprintln!("greprs: unknown file type '{}' (see --type-list)", name);
+                std::process::exit(1);
+            }
+        }
+    }
+    for name in &args.file_type_not {
+        match type_registry.globs_for(name) {
+            Some(globs) => type_filters.push((GlobSet::build(globs), false)),
+            None => {
+                eprintln!("greprs: unknown file type '{}' (see --type-list)", name);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Combine every pattern source (positional PATTERN, repeated -e, and -f/--file, one
+    // alternative per line) into a single alternation, like GNU grep does. When -e/-f already
+    // supplied a pattern, the positional `pattern` clap captured is actually the first FILE
+    // (GNU grep's convention: with -e/-f present, every positional is a file), so it's put
+    // back at the front of the file list instead of being folded into the pattern.
+    let mut alternatives: Vec<String> = args.regexp.clone();
+    let mut files = args.files.clone();
+    if !args.regexp.is_empty() || args.pattern_file.is_some() {
+        if let Some(pattern) = &args.pattern {
+            files.insert(0, std::path::PathBuf::from(pattern));
+        }
+    } else if let Some(pattern) = &args.pattern {
+        alternatives.push(pattern.clone());
+    }
+    if let Some(pattern_file) = &args.pattern_file {
+        let contents = std::fs::read_to_string(pattern_file)?;
+        alternatives.extend(contents.lines().map(|l| l.to_string()));
+    }
+    if alternatives.is_empty() {
+        eprintln!("greprs: no pattern given (use PATTERN, -e, or -f)");
+        std::process::exit(1);
+    }
+    let multi_pattern = alternatives.len() > 1;
+    let combined_pattern = combine_alternatives(&alternatives, args.fixed_strings, args.pcre2);
+
+    // When multiple alternatives were combined above, each one already had its own escaping
+    // (fixed-string or basic-regex) applied before being joined with a real `|`. Re-running
+    // that escaping on the combined string here would also escape the alternation syntax, so
+    // `fixed_strings` is cleared and `extended_regexp` set to skip it; a single pattern is
+    // untouched by `combine_alternatives` and still gets its normal escaping below.
     let regex_config = RegexConfig {
         ignore_case: args.ignore_case,
         word_regexp: args.word_regexp,
         line_regexp: args.line_regexp,
-        fixed_strings: args.fixed_strings,
+        fixed_strings: args.fixed_strings && !multi_pattern,
+        extended_regexp: multi_pattern,
+        smart_case: args.smart_case,
+        pcre2: args.pcre2,
+        ..RegexConfig::default()
     };
-    
-    let regex = build_regex(&args.pattern, &regex_config)
+
+    let regex = build_matcher(&combined_pattern, &regex_config)
         .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
-    
+
+    let binary_detection = if args.text {
+        BinaryDetection::Disabled
+    } else if args.ignore_binary {
+        BinaryDetection::Skip
+    } else if args.binary {
+        BinaryDetection::Convert
+    } else {
+        BinaryDetection::Quit
+    };
+
     // Auto-detect if we should show filenames (like grep does)
     // Show filenames if: multiple files OR --with-filename OR (not --no-filename AND multiple files)
     let should_show_filename = if args.no_filename {
@@ -50,7 +138,7 @@ fn main() -> io::Result<()> {
     } else if args.with_filename {
         true
     } else {
-        args.files.len() > 1
+        files.len() > 1
     };
     
     let config = SearchConfig {
@@ -70,26 +158,60 @@ fn main() -> io::Result<()> {
         null_data: args.null_data,
         null: args.null,
         text: args.text,
-        ignore_binary: args.ignore_binary,
         no_messages: args.no_messages,
-        exclude_patterns,
-        include_patterns,
+        exclude_globs,
+        include_globs,
+        type_filters,
         use_color,
+        json: args.json,
+        no_ignore: args.no_ignore,
+        show_hidden: args.hidden,
+        encoding: args.encoding.clone(),
+        search_compressed: args.search_zip,
+        preprocessor: PreprocessorConfig {
+            command: args.pre.clone(),
+            globs: GlobSet::build(&args.pre_glob),
+            use_builtins: args.pre_builtins,
+        },
+        binary_detection,
+        stats: if args.stats { Some(Arc::new(Stats::default())) } else { None },
     };
 
     // Use buffered writer for better performance
     let stdout = io::stdout();
     let mut handle = BufWriter::with_capacity(64 * 1024, stdout.lock());
+    let start_time = Instant::now();
 
-    if args.files.is_empty() {
-        eprintln!("Reading from stdin not yet implemented, please provide file arguments");
-        std::process::exit(1);
+    // Size the rayon worker pool that `visit_path` fans out onto for recursive search.
+    // Defaults to the number of available CPUs, overridable via `--threads`.
+    let num_threads = match args.threads {
+        Some(n) if n > 0 => n,
+        _ => num_cpus::get(),
+    };
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+
+    if files.is_empty() {
+        search_stdin(&regex, &config, &mut handle)?;
     } else {
-        for file_path in &args.files {
-            visit_path(&regex, file_path, &config, args.recursive, &mut handle)?;
-        }
+        pool.install(|| -> io::Result<()> {
+            for file_path in &files {
+                if file_path.as_os_str() == "-" {
+                    search_stdin(&regex, &config, &mut handle)?;
+                } else {
+                    visit_path(&regex, file_path, &config, args.recursive, &mut handle)?;
+                }
+            }
+            Ok(())
+        })?;
     }
-    
+
+    if let Some(stats) = &config.stats {
+        stats.print_summary(&mut handle, start_time.elapsed())?;
+    }
+
     // Ensure all output is flushed
     handle.flush()?;
 
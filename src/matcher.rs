@@ -0,0 +1,77 @@
+//! Abstracts over the default `regex` engine and the optional PCRE2 engine so the search
+//! loop in `search.rs` doesn't need to care which one produced a match. PCRE2 support is
+//! gated behind the `pcre2` cargo feature so builds without the C dependency still work.
+
+use regex::Regex;
+
+#[cfg(feature = "pcre2")]
+use pcre2::bytes::Regex as Pcre2Regex;
+
+/// A single match within a line, duck-typed to look like `regex::Match` (`as_str`/`start`/
+/// `end`) so call sites don't need to special-case which engine produced it.
+pub struct MatchSpan<'a> {
+    start: usize,
+    end: usize,
+    as_str: &'a str,
+}
+
+impl<'a> MatchSpan<'a> {
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.as_str
+    }
+}
+
+/// The compiled pattern, backed by either the `regex` crate (default) or PCRE2 (`-P`), which
+/// supports backreferences and lookaround that `regex` can't express.
+pub enum Matcher {
+    Regex(Regex),
+    #[cfg(feature = "pcre2")]
+    Pcre2(Pcre2Regex),
+}
+
+impl Matcher {
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            Matcher::Regex(re) => re.is_match(text),
+            #[cfg(feature = "pcre2")]
+            Matcher::Pcre2(re) => re.is_match(text.as_bytes()).unwrap_or(false),
+        }
+    }
+
+    pub fn find_iter<'a>(&self, text: &'a str) -> Vec<MatchSpan<'a>> {
+        match self {
+            Matcher::Regex(re) => re
+                .find_iter(text)
+                .map(|m| MatchSpan {
+                    start: m.start(),
+                    end: m.end(),
+                    as_str: m.as_str(),
+                })
+                .collect(),
+            #[cfg(feature = "pcre2")]
+            Matcher::Pcre2(re) => re
+                .find_iter(text.as_bytes())
+                .filter_map(|m| m.ok())
+                .map(|m| MatchSpan {
+                    start: m.start(),
+                    end: m.end(),
+                    as_str: &text[m.start()..m.end()],
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Regex> for Matcher {
+    fn from(re: Regex) -> Self {
+        Matcher::Regex(re)
+    }
+}
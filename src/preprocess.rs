@@ -0,0 +1,76 @@
+//! `--pre` preprocessor/adapter pipeline, following the adapter pattern from ripgrep-all:
+//! non-text files (PDFs, office documents, ...) are piped through an external command and
+//! the command's stdout is searched as if it were the file's contents.
+
+use crate::globset::GlobSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Built-in adapters keyed by extension, used only when `--pre-builtins` opts into them and
+/// the user hasn't overridden `--pre`. Users can always supply their own `--pre COMMAND` to
+/// take precedence over these defaults.
+const BUILTIN_ADAPTERS: &[(&str, &str)] = &[("pdf", "pdftotext")];
+
+#[derive(Debug, Default)]
+pub struct PreprocessorConfig {
+    /// An explicit `--pre COMMAND`, applied to every file unless restricted by `globs`.
+    pub command: Option<PathBuf>,
+    /// `--pre-glob` patterns restricting which files `command` applies to. Empty means "all
+    /// files that reach the preprocessor stage".
+    pub globs: GlobSet,
+    /// `--pre-builtins`: fall back to `BUILTIN_ADAPTERS` by extension when `command` is unset.
+    /// Off by default so a plain recursive search never implicitly shells out to a tool like
+    /// `pdftotext` that may not be installed.
+    pub use_builtins: bool,
+}
+
+/// Runs the configured (or, if `--pre-builtins` is set, built-in) preprocessor for `path`,
+/// returning its stdout as text, or `None` if no preprocessor applies and `path` should be
+/// read normally.
+pub fn run(path: &Path, config: &PreprocessorConfig) -> io::Result<Option<String>> {
+    let filename = match path.file_name().and_then(|n| n.to_str()) {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+
+    let command = if let Some(cmd) = &config.command {
+        if !config.globs.is_empty() && config.globs.matches(filename).is_empty() {
+            return Ok(None);
+        }
+        Some(cmd.clone())
+    } else if config.use_builtins {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| {
+                BUILTIN_ADAPTERS
+                    .iter()
+                    .find(|(adapter_ext, _)| *adapter_ext == ext)
+                    .map(|(_, cmd)| PathBuf::from(cmd))
+            })
+    } else {
+        None
+    };
+
+    let Some(command) = command else {
+        return Ok(None);
+    };
+
+    let output = Command::new(&command).arg(path).output()?;
+    if !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!(
+                "preprocessor '{}' exited with {} for {}",
+                command.display(),
+                output.status,
+                path.display()
+            ),
+        ));
+    }
+
+    String::from_utf8(output.stdout)
+        .map(Some)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "preprocessor output wasn't valid UTF-8"))
+}
+
@@ -1,10 +1,94 @@
 use rayon::prelude::*;
-use regex::Regex;
+use crate::matcher::Matcher;
+use std::borrow::Cow;
 use std::fs;
 use std::io::{self, Read, Write};
 use std::path::Path;
-use glob::Pattern as GlobPattern;
 use memmap2::Mmap;
+use crate::decode;
+use crate::gitignore::IgnoreStack;
+use crate::globset::GlobSet;
+use crate::json::{self, BeginData, ContextData, EndData, JsonEvent, MatchData, SubMatch, Text};
+use crate::preprocess::{self, PreprocessorConfig};
+use crate::stats::Stats;
+use std::sync::Arc;
+
+/// How a file whose first [`BINARY_SCAN_LIMIT`] bytes contain a NUL byte is handled, mirroring
+/// ripgrep's `-a`/`-I`/`--binary` trio. Detection is content-based rather than guessed from the
+/// file extension, so it also catches extensionless binaries and misses false positives on
+/// text files that merely have an unusual extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinaryDetection {
+    /// Stop searching the file as soon as a NUL byte is seen, printing the standard
+    /// `Binary file <path> matches` notice if the pattern matched anywhere before that point.
+    /// This is grep's traditional default.
+    #[default]
+    Quit,
+    /// Same as `Quit` but without the notice, used for `-I`/`--ignore-binary`: the file is
+    /// skipped as if it had never matched.
+    Skip,
+    /// Replace NUL bytes with newlines so the rest of the file can still be searched and
+    /// matches reported, suppressing the raw binary noise. Used for `--binary`.
+    Convert,
+    /// Don't treat NUL bytes specially at all; used for `-a`/`--text`.
+    Disabled,
+}
+
+/// How many leading bytes of a file are scanned for a NUL byte when deciding if it's binary.
+const BINARY_SCAN_LIMIT: usize = 8192;
+
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(BINARY_SCAN_LIMIT).any(|&b| b == 0)
+}
+
+/// Applies `config.binary_detection` to raw file bytes before they're transcoded and split
+/// into lines. Returns `Some(bytes)` (unchanged, or with NULs converted) when the caller should
+/// keep processing the file, or `None` once the file has already been fully handled (the
+/// `Quit`/`Skip` cases), in which case the caller should stop.
+///
+/// Callers must only invoke this on bytes that `decode::has_explicit_encoding` says will be
+/// treated as plain UTF-8: an encoding like UTF-16 packs a NUL into every other byte, so
+/// scanning its raw bytes for NULs here would misdetect ordinary text as binary. Callers must
+/// also skip this when `config.null_data` is set, since NUL is then the active record
+/// separator rather than a binary-file signal (ripgrep excludes the active line terminator
+/// from binary detection the same way).
+fn apply_binary_detection<'a, W: Write>(
+    raw: Cow<'a, [u8]>,
+    path: &Path,
+    regex: &Matcher,
+    config: &SearchConfig,
+    writer: &mut W,
+) -> io::Result<Option<Cow<'a, [u8]>>> {
+    if config.binary_detection == BinaryDetection::Disabled || !looks_binary(&raw) {
+        return Ok(Some(raw));
+    }
+
+    match config.binary_detection {
+        BinaryDetection::Quit | BinaryDetection::Skip => {
+            let announce = config.binary_detection == BinaryDetection::Quit
+                && !config.no_messages
+                && !config.quiet;
+            if announce {
+                let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+                let text = String::from_utf8_lossy(&raw[..end]);
+                if regex.is_match(&text) != config.invert_match {
+                    writeln!(writer, "Binary file {} matches", path.display())?;
+                }
+            }
+            Ok(None)
+        }
+        BinaryDetection::Convert => {
+            let mut converted = raw.into_owned();
+            for byte in converted.iter_mut() {
+                if *byte == 0 {
+                    *byte = b'\n';
+                }
+            }
+            Ok(Some(Cow::Owned(converted)))
+        }
+        BinaryDetection::Disabled => unreachable!(),
+    }
+}
 
 #[derive(Debug, Default)]
 pub struct SearchConfig {
@@ -25,11 +109,25 @@ pub struct SearchConfig {
     pub null_data: bool,
     pub null: bool,
     pub text: bool,
-    pub ignore_binary: bool,
     pub no_messages: bool,
-    pub exclude_patterns: Vec<GlobPattern>,
-    pub include_patterns: Vec<GlobPattern>,
+    pub exclude_globs: GlobSet,
+    pub include_globs: GlobSet,
+    /// One entry per `--type`/`--type-not NAME`, each already resolved to the `GlobSet` for
+    /// that type; `true` means the file must match (`--type`), `false` means it must not
+    /// (`--type-not`). Checked in `should_process_file` after `exclude_globs`/`include_globs`.
+    pub type_filters: Vec<(GlobSet, bool)>,
     pub use_color: bool,
+    pub json: bool,
+    pub no_ignore: bool,
+    pub show_hidden: bool,
+    pub encoding: Option<String>,
+    pub search_compressed: bool,
+    pub preprocessor: PreprocessorConfig,
+    pub binary_detection: BinaryDetection,
+    /// Shared accumulator for `--stats`; `None` means stats collection is disabled. Shared via
+    /// `Arc` so every rayon worker searching a different file can fold its results into the
+    /// same totals without a lock.
+    pub stats: Option<Arc<Stats>>,
 }
 
 impl SearchConfig {
@@ -47,17 +145,35 @@ impl SearchConfig {
 }
 
 pub fn visit_path<W: Write>(
-    regex: &Regex,
+    regex: &Matcher,
     path: &Path,
     config: &SearchConfig,
     recursive: bool,
     writer: &mut W
+) -> io::Result<()> {
+    let ignore = IgnoreStack::new();
+    visit_path_with_ignore(regex, path, config, recursive, writer, &ignore)
+}
+
+fn visit_path_with_ignore<W: Write>(
+    regex: &Matcher,
+    path: &Path,
+    config: &SearchConfig,
+    recursive: bool,
+    writer: &mut W,
+    ignore: &IgnoreStack,
 ) -> io::Result<()> {
     if path.is_dir() {
+        let ignore = if recursive && !config.no_ignore {
+            ignore.push_dir(path)
+        } else {
+            ignore.clone()
+        };
+
         let entries: Result<Vec<_>, io::Error> = fs::read_dir(path)?
             .collect();
         let entries = entries?;
-        
+
         if recursive {
             // Use parallel processing only for larger directory sets
             if entries.len() > 20 {
@@ -65,9 +181,12 @@ pub fn visit_path<W: Write>(
                     .into_par_iter()
                     .filter_map(|entry| {
                         let path = entry.path();
+                        if is_ignored(&path, config, &ignore) {
+                            return None;
+                        }
                         if should_process_file(&path, config) || path.is_dir() {
                             let mut buffer = Vec::with_capacity(4096);
-                            if let Err(err) = visit_path(regex, &path, config, recursive, &mut buffer) {
+                            if let Err(err) = visit_path_with_ignore(regex, &path, config, recursive, &mut buffer, &ignore) {
                                 if !config.no_messages {
                                     eprintln!("greprs: {}: {}", path.display(), err);
                                 }
@@ -82,7 +201,7 @@ pub fn visit_path<W: Write>(
                         }
                     })
                     .collect();
-                
+
                 for result in results {
                     writer.write_all(&result)?;
                 }
@@ -90,8 +209,11 @@ pub fn visit_path<W: Write>(
                 // Sequential processing for smaller sets to avoid overhead
                 for entry in entries {
                     let path = entry.path();
+                    if is_ignored(&path, config, &ignore) {
+                        continue;
+                    }
                     if should_process_file(&path, config) || path.is_dir() {
-                        visit_path(regex, &path, config, recursive, writer)?;
+                        visit_path_with_ignore(regex, &path, config, recursive, writer, &ignore)?;
                     }
                 }
             }
@@ -112,6 +234,25 @@ pub fn visit_path<W: Write>(
     Ok(())
 }
 
+fn is_ignored(path: &Path, config: &SearchConfig, ignore: &IgnoreStack) -> bool {
+    if !config.show_hidden && is_hidden(path) {
+        return true;
+    }
+    if config.no_ignore {
+        return false;
+    }
+    ignore.is_ignored(path, path.is_dir())
+}
+
+/// Whether `path`'s file name starts with `.`, matching shells' definition of a hidden file.
+/// Skipped by default during recursive search, like ripgrep; `--hidden` opts back in.
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|name| name.starts_with('.'))
+        .unwrap_or(false)
+}
+
 fn should_process_file(path: &Path, config: &SearchConfig) -> bool {
     // Early exit for non-files
     if !path.is_file() {
@@ -124,61 +265,93 @@ fn should_process_file(path: &Path, config: &SearchConfig) -> bool {
     };
     
     // Check exclude patterns first (more common case)
-    for pattern in &config.exclude_patterns {
-        if pattern.matches(filename) {
-            return false;
-        }
+    if !config.exclude_globs.matches(filename).is_empty() {
+        return false;
     }
-    
+
     // Check include patterns (if any specified, file must match at least one)
-    if !config.include_patterns.is_empty() {
-        let matches_include = config.include_patterns.iter()
-            .any(|pattern| pattern.matches(filename));
-        if !matches_include {
-            return false;
-        }
+    if !config.include_globs.is_empty() && config.include_globs.matches(filename).is_empty() {
+        return false;
     }
-    
-    // Quick binary detection without reading file if ignore_binary is set
-    if config.ignore_binary && !config.text {
-        // Simple heuristic: check file extension first
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            match ext {
-                "bin" | "exe" | "dll" | "so" | "dylib" | "o" | "a" | "lib" | 
-                "jpg" | "jpeg" | "png" | "gif" | "pdf" | "zip" | "tar" | "gz" => {
-                    return false;
-                }
-                _ => {}
-            }
+
+    // Check --type/--type-not filters
+    for (globs, must_match) in &config.type_filters {
+        let matched = !globs.matches(filename).is_empty();
+        if matched != *must_match {
+            return false;
         }
     }
-    
+
     true
 }
 
+/// Searches standard input as if it were a file named `(standard input)`, honoring the same
+/// null-data splitting, context, counting, and `--only-matching` behavior as `search_file`.
+pub fn search_stdin<W: Write>(
+    regex: &Matcher,
+    config: &SearchConfig,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut contents = String::new();
+    io::stdin().read_to_string(&mut contents)?;
+    let path = Path::new("(standard input)");
+    search_decoded_contents(regex, path, &contents, config, writer)
+}
+
 pub fn search_file<W: Write>(
-    regex: &Regex,
+    regex: &Matcher,
     path: &Path,
     config: &SearchConfig,
     writer: &mut W
 ) -> io::Result<()> {
+    if let Some(contents) = preprocess::run(path, &config.preprocessor)? {
+        return search_decoded_contents(regex, path, &contents, config, writer);
+    }
+
+    if config.search_compressed {
+        if let Some(raw) = decode::decompress(path)? {
+            let contents = decode::transcode(&raw, config.encoding.as_deref())?;
+            return search_decoded_contents(regex, path, &contents, config, writer);
+        }
+    }
+
     let metadata = fs::metadata(path)?;
     let file_size = metadata.len() as usize;
-    
+
     if file_size == 0 {
         return Ok(());
     }
-    
+
     // Use memory mapping for larger files to avoid loading into memory
     if file_size > 1024 * 1024 {
         return search_mmap_file(regex, path, config, writer);
     }
-    
-    // For smaller files, read into string with pre-allocated capacity
+
+    // For smaller files, read into a byte buffer and let `decode::transcode` sniff a BOM
+    // (or apply `--encoding`); already-UTF-8 content with no BOM is borrowed as-is, so this
+    // keeps the zero-copy fast path for the common case.
     let mut file = fs::File::open(path)?;
-    let mut contents = String::with_capacity(file_size);
-    file.read_to_string(&mut contents)?;
-    
+    let mut raw = Vec::with_capacity(file_size);
+    file.read_to_end(&mut raw)?;
+    let raw = if config.null_data || decode::has_explicit_encoding(config.encoding.as_deref(), &raw) {
+        Cow::Owned(raw)
+    } else {
+        match apply_binary_detection(Cow::Owned(raw), path, regex, config, writer)? {
+            Some(raw) => raw,
+            None => return Ok(()),
+        }
+    };
+    let contents = decode::transcode(&raw, config.encoding.as_deref())?;
+    search_decoded_contents(regex, path, &contents, config, writer)
+}
+
+fn search_decoded_contents<W: Write>(
+    regex: &Matcher,
+    path: &Path,
+    contents: &str,
+    config: &SearchConfig,
+    writer: &mut W,
+) -> io::Result<()> {
     if config.null_data {
         let lines: Vec<&str> = contents.split('\0').collect();
         search_lines_optimized(regex, path, &lines, config, writer)
@@ -189,17 +362,25 @@ pub fn search_file<W: Write>(
 }
 
 fn search_mmap_file<W: Write>(
-    regex: &Regex,
+    regex: &Matcher,
     path: &Path,
     config: &SearchConfig,
     writer: &mut W
 ) -> io::Result<()> {
     let file = fs::File::open(path)?;
     let mmap = unsafe { Mmap::map(&file)? };
-    let contents = std::str::from_utf8(&mmap).map_err(|_| {
-        io::Error::new(io::ErrorKind::InvalidData, "File contains invalid UTF-8")
-    })?;
-    
+    let raw = if config.null_data || decode::has_explicit_encoding(config.encoding.as_deref(), &mmap) {
+        Cow::Borrowed(&mmap[..])
+    } else {
+        match apply_binary_detection(Cow::Borrowed(&mmap[..]), path, regex, config, writer)? {
+            Some(raw) => raw,
+            None => return Ok(()),
+        }
+    };
+    // `decode::transcode` sniffs a UTF-8/UTF-16 BOM (or uses `--encoding` if given) and
+    // otherwise borrows the mmap as-is, so already-UTF-8 files keep the old zero-copy path.
+    let contents = decode::transcode(&raw, config.encoding.as_deref())?;
+
     if config.null_data {
         let lines: Vec<&str> = contents.split('\0').collect();
         search_lines_optimized(regex, path, &lines, config, writer)
@@ -209,57 +390,143 @@ fn search_mmap_file<W: Write>(
     }
 }
 
+/// Accumulates `--stats` totals for one file as its lines are walked by whichever mode-specific
+/// loop in `search_lines_optimized` (or `search_with_context`/`search_without_context`/
+/// `search_json_lines`) is actually doing the work, instead of re-scanning the file in a
+/// separate pass. Folded into the shared `Stats` via `finish` once that loop is done, so it
+/// naturally respects whatever `--max-count` truncation the loop already applied.
+#[derive(Default)]
+struct StatsAccum {
+    matched_lines: u64,
+    matches: u64,
+    bytes_searched: u64,
+}
+
+impl StatsAccum {
+    fn note_line(&mut self, line: &str) {
+        self.bytes_searched += line.len() as u64 + 1;
+    }
+
+    /// Records one matched line; `matches` counts once per submatch, or once for `-v`, since
+    /// there's no single regex match to count submatches of.
+    fn note_match(&mut self, regex: &Matcher, line: &str, invert_match: bool) {
+        self.matched_lines += 1;
+        self.matches += if invert_match {
+            1
+        } else {
+            regex.find_iter(line).len().max(1) as u64
+        };
+    }
+
+    fn finish(self, stats: &Stats) {
+        stats.record_file(self.matched_lines, self.matches, self.bytes_searched);
+    }
+}
+
 fn search_lines_optimized<W: Write>(
-    regex: &Regex,
+    regex: &Matcher,
     path: &Path,
     lines: &[&str],
     config: &SearchConfig,
     writer: &mut W
 ) -> io::Result<()> {
     let mut count = 0;
-    
+
     // Pre-compute these to avoid repeated checks
     let show_filename = config.with_filename && !config.no_filename;
     let has_context = config.has_context();
-    
-    // Early exit optimizations for simple cases
+
+    if config.json {
+        return search_json_lines(regex, path, lines, config, writer);
+    }
+
+    let mut stats_accum = config.stats.as_ref().map(|_| StatsAccum::default());
+
+    // Early exit optimizations for simple cases. When `--stats` is enabled these still walk
+    // every line (rather than stopping at the first match) so the totals are accurate, since
+    // these modes otherwise never look past the first match.
     if config.quiet {
-        // For quiet mode, just check if any line matches
         for line in lines {
+            if let Some(accum) = stats_accum.as_mut() {
+                accum.note_line(line);
+            }
             if regex.is_match(line) != config.invert_match {
-                return Ok(());
+                if let Some(accum) = stats_accum.as_mut() {
+                    accum.note_match(regex, line, config.invert_match);
+                }
+                if stats_accum.is_none() {
+                    return Ok(());
+                }
             }
         }
+        if let (Some(accum), Some(stats)) = (stats_accum, &config.stats) {
+            accum.finish(stats);
+        }
         return Ok(());
     }
-    
+
     if config.files_with_matches {
-        // For -l flag, just check if any line matches
+        let mut any_match = false;
         for line in lines {
+            if let Some(accum) = stats_accum.as_mut() {
+                accum.note_line(line);
+            }
             if regex.is_match(line) != config.invert_match {
-                writeln!(writer, "{}", path.display())?;
-                return Ok(());
+                if let Some(accum) = stats_accum.as_mut() {
+                    accum.note_match(regex, line, config.invert_match);
+                }
+                if stats_accum.is_none() {
+                    writeln!(writer, "{}", path.display())?;
+                    return Ok(());
+                }
+                any_match = true;
             }
         }
+        if any_match {
+            writeln!(writer, "{}", path.display())?;
+        }
+        if let (Some(accum), Some(stats)) = (stats_accum, &config.stats) {
+            accum.finish(stats);
+        }
         return Ok(());
     }
-    
+
     if config.files_without_match {
-        // For -L flag, check if no lines match
+        let mut any_match = false;
         for line in lines {
+            if let Some(accum) = stats_accum.as_mut() {
+                accum.note_line(line);
+            }
             if regex.is_match(line) != config.invert_match {
-                return Ok(()); // Found a match, don't print filename
+                if let Some(accum) = stats_accum.as_mut() {
+                    accum.note_match(regex, line, config.invert_match);
+                }
+                if stats_accum.is_none() {
+                    return Ok(()); // Found a match, don't print filename
+                }
+                any_match = true;
             }
         }
-        writeln!(writer, "{}", path.display())?;
+        if let (Some(accum), Some(stats)) = (stats_accum, &config.stats) {
+            accum.finish(stats);
+        }
+        if !any_match {
+            writeln!(writer, "{}", path.display())?;
+        }
         return Ok(());
     }
-    
+
     if config.count {
         // For count mode, just count matches
         for line in lines {
+            if let Some(accum) = stats_accum.as_mut() {
+                accum.note_line(line);
+            }
             if regex.is_match(line) != config.invert_match {
                 count += 1;
+                if let Some(accum) = stats_accum.as_mut() {
+                    accum.note_match(regex, line, config.invert_match);
+                }
                 if let Some(max) = config.max_count {
                     if count >= max {
                         break;
@@ -267,13 +534,16 @@ fn search_lines_optimized<W: Write>(
                 }
             }
         }
+        if let (Some(accum), Some(stats)) = (stats_accum, &config.stats) {
+            accum.finish(stats);
+        }
         if show_filename {
             write!(writer, "{}:", path.display())?;
         }
         writeln!(writer, "{}", count)?;
         return Ok(());
     }
-    
+
     // Full search with context handling
     if has_context {
         search_with_context(regex, path, lines, config, writer)
@@ -282,8 +552,140 @@ fn search_lines_optimized<W: Write>(
     }
 }
 
+fn json_path(path: &Path) -> Text {
+    Text::from_bytes(path.as_os_str().to_string_lossy().as_bytes())
+}
+
+/// Emits one JSON object per event (`begin`/`match`/`context`/`end`), mirroring ripgrep's
+/// `--json` printer. Every reported line carries its absolute byte offset, and matching
+/// lines additionally carry submatch spans computed from `regex.find_iter`; context lines
+/// from `-A`/`-B`/`-C` are reported as `context` events with no submatches.
+fn search_json_lines<W: Write>(
+    regex: &Matcher,
+    path: &Path,
+    lines: &[&str],
+    config: &SearchConfig,
+    writer: &mut W,
+) -> io::Result<()> {
+    json::write_event(writer, &JsonEvent::Begin(BeginData { path: json_path(path) }))?;
+
+    let before_context = config.effective_before_context();
+    let after_context = config.effective_after_context();
+    let mut context_buffer: std::collections::VecDeque<(usize, &str, u64)> =
+        std::collections::VecDeque::with_capacity(before_context);
+    let mut after_lines_remaining = 0;
+    let mut last_reported_line = None;
+
+    let mut stats_accum = config.stats.as_ref().map(|_| StatsAccum::default());
+
+    let mut count = 0;
+    let mut byte_pos: u64 = 0;
+    for (line_num, line) in lines.iter().enumerate() {
+        if let Some(accum) = stats_accum.as_mut() {
+            accum.note_line(line);
+        }
+        let is_match = regex.is_match(line) != config.invert_match;
+
+        if is_match {
+            count += 1;
+            if let Some(max) = config.max_count {
+                if count > max {
+                    break;
+                }
+            }
+
+            if before_context > 0 {
+                for &(ctx_line_num, ctx_line, ctx_offset) in &context_buffer {
+                    if Some(ctx_line_num) != last_reported_line {
+                        json::write_event(
+                            writer,
+                            &JsonEvent::Context(ContextData {
+                                path: json_path(path),
+                                lines: Text::from_bytes(ctx_line.as_bytes()),
+                                line_number: if config.line_number {
+                                    Some((ctx_line_num + 1) as u64)
+                                } else {
+                                    None
+                                },
+                                absolute_offset: ctx_offset,
+                            }),
+                        )?;
+                    }
+                }
+            }
+
+            let submatches: Vec<SubMatch> = regex
+                .find_iter(line)
+                .map(|m| SubMatch {
+                    text: Text::from_bytes(m.as_str().as_bytes()),
+                    start: m.start(),
+                    end: m.end(),
+                })
+                .collect();
+
+            if let Some(accum) = stats_accum.as_mut() {
+                accum.matched_lines += 1;
+                accum.matches += if config.invert_match { 1 } else { submatches.len().max(1) as u64 };
+            }
+
+            let match_data = MatchData {
+                path: json_path(path),
+                lines: Text::from_bytes(line.as_bytes()),
+                line_number: if config.line_number {
+                    Some((line_num + 1) as u64)
+                } else {
+                    None
+                },
+                absolute_offset: byte_pos,
+                submatches,
+            };
+            json::write_event(writer, &JsonEvent::Match(match_data))?;
+
+            last_reported_line = Some(line_num);
+            after_lines_remaining = after_context;
+        } else if after_lines_remaining > 0 {
+            json::write_event(
+                writer,
+                &JsonEvent::Context(ContextData {
+                    path: json_path(path),
+                    lines: Text::from_bytes(line.as_bytes()),
+                    line_number: if config.line_number {
+                        Some((line_num + 1) as u64)
+                    } else {
+                        None
+                    },
+                    absolute_offset: byte_pos,
+                }),
+            )?;
+            after_lines_remaining -= 1;
+        }
+
+        if before_context > 0 {
+            context_buffer.push_back((line_num, line, byte_pos));
+            if context_buffer.len() > before_context {
+                context_buffer.pop_front();
+            }
+        }
+
+        byte_pos += line.len() as u64 + 1; // +1 for the newline stripped by `lines()`
+    }
+
+    json::write_event(
+        writer,
+        &JsonEvent::End(EndData {
+            path: json_path(path),
+        }),
+    )?;
+
+    if let (Some(accum), Some(stats)) = (stats_accum, &config.stats) {
+        accum.finish(stats);
+    }
+
+    Ok(())
+}
+
 fn search_without_context<W: Write>(
-    regex: &Regex,
+    regex: &Matcher,
     path: &Path,
     lines: &[&str],
     config: &SearchConfig,
@@ -291,37 +693,49 @@ fn search_without_context<W: Write>(
 ) -> io::Result<()> {
     let mut count = 0;
     let show_filename = config.with_filename && !config.no_filename;
-    
+    let mut stats_accum = config.stats.as_ref().map(|_| StatsAccum::default());
+
     let mut byte_pos = 0;
     for (line_num, line) in lines.iter().enumerate() {
+        if let Some(accum) = stats_accum.as_mut() {
+            accum.note_line(line);
+        }
         let is_match = regex.is_match(line) != config.invert_match;
-        
+
         if is_match {
             count += 1;
-            
+
             if let Some(max) = config.max_count {
                 if count > max {
                     break;
                 }
             }
-            
+
+            if let Some(accum) = stats_accum.as_mut() {
+                accum.note_match(regex, line, config.invert_match);
+            }
+
             if config.only_matching {
                 print_only_matches_fast(writer, path, line_num, line, regex, config, byte_pos)?;
             } else {
                 print_line_fast(writer, path, line_num, line, config, show_filename, byte_pos)?;
             }
         }
-        
+
         if config.byte_offset {
             byte_pos += line.len() + 1; // +1 for newline
         }
     }
-    
+
+    if let (Some(accum), Some(stats)) = (stats_accum, &config.stats) {
+        accum.finish(stats);
+    }
+
     Ok(())
 }
 
 fn search_with_context<W: Write>(
-    regex: &Regex,
+    regex: &Matcher,
     path: &Path,
     lines: &[&str],
     config: &SearchConfig,
@@ -331,24 +745,32 @@ fn search_with_context<W: Write>(
     let show_filename = config.with_filename && !config.no_filename;
     let before_context = config.effective_before_context();
     let after_context = config.effective_after_context();
-    
-    let mut context_buffer: std::collections::VecDeque<(usize, &str)> = 
+    let mut stats_accum = config.stats.as_ref().map(|_| StatsAccum::default());
+
+    let mut context_buffer: std::collections::VecDeque<(usize, &str)> =
         std::collections::VecDeque::with_capacity(before_context);
     let mut after_lines_remaining = 0;
     let mut last_match_line = None;
-    
+
     for (line_num, line) in lines.iter().enumerate() {
+        if let Some(accum) = stats_accum.as_mut() {
+            accum.note_line(line);
+        }
         let is_match = regex.is_match(line) != config.invert_match;
-        
+
         if is_match {
             count += 1;
-            
+
             if let Some(max) = config.max_count {
                 if count > max {
                     break;
                 }
             }
-            
+
+            if let Some(accum) = stats_accum.as_mut() {
+                accum.note_match(regex, line, config.invert_match);
+            }
+
             // Print before context
             if before_context > 0 {
                 for (ctx_line_num, ctx_line) in &context_buffer {
@@ -381,7 +803,11 @@ fn search_with_context<W: Write>(
             }
         }
     }
-    
+
+    if let (Some(accum), Some(stats)) = (stats_accum, &config.stats) {
+        accum.finish(stats);
+    }
+
     Ok(())
 }
 
@@ -444,7 +870,7 @@ fn print_only_matches_fast<W: Write>(
     path: &Path,
     line_num: usize,
     line: &str,
-    regex: &Regex,
+    regex: &Matcher,
     config: &SearchConfig,
     byte_offset: usize,
 ) -> io::Result<()> {
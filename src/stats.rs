@@ -0,0 +1,42 @@
+//! Aggregate run statistics for `--stats`, mirroring ripgrep's summary block. Counters are
+//! atomics so every worker thread in the `rayon` pool can update them without a lock, and the
+//! final values are read back once the whole search has finished to print the summary.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+pub struct Stats {
+    matched_lines: AtomicU64,
+    matches: AtomicU64,
+    files_searched: AtomicU64,
+    files_with_matches: AtomicU64,
+    bytes_searched: AtomicU64,
+}
+
+impl Stats {
+    /// Folds one file's results into the running totals. Called once per file searched,
+    /// regardless of whether it matched.
+    pub fn record_file(&self, matched_lines: u64, matches: u64, bytes_searched: u64) {
+        self.files_searched.fetch_add(1, Ordering::Relaxed);
+        if matched_lines > 0 {
+            self.files_with_matches.fetch_add(1, Ordering::Relaxed);
+        }
+        self.matched_lines.fetch_add(matched_lines, Ordering::Relaxed);
+        self.matches.fetch_add(matches, Ordering::Relaxed);
+        self.bytes_searched.fetch_add(bytes_searched, Ordering::Relaxed);
+    }
+
+    /// Prints the `--stats` summary block after all files have been searched.
+    pub fn print_summary<W: Write>(&self, writer: &mut W, elapsed: Duration) -> io::Result<()> {
+        writeln!(writer)?;
+        writeln!(writer, "{} matches", self.matches.load(Ordering::Relaxed))?;
+        writeln!(writer, "{} matched lines", self.matched_lines.load(Ordering::Relaxed))?;
+        writeln!(writer, "{} files contained matches", self.files_with_matches.load(Ordering::Relaxed))?;
+        writeln!(writer, "{} files searched", self.files_searched.load(Ordering::Relaxed))?;
+        writeln!(writer, "{} bytes searched", self.bytes_searched.load(Ordering::Relaxed))?;
+        writeln!(writer, "{:.6} seconds", elapsed.as_secs_f64())?;
+        Ok(())
+    }
+}
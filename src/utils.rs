@@ -1,8 +1,20 @@
 use regex::{Regex, RegexBuilder};
+use crate::matcher::Matcher;
+
+#[cfg(feature = "pcre2")]
+use pcre2::bytes::RegexBuilder as Pcre2RegexBuilder;
 
 pub fn build_regex(pattern: &str, config: &RegexConfig) -> Result<Regex, regex::Error> {
+    let ignore_case = if config.ignore_case {
+        true
+    } else if config.smart_case {
+        !pattern_has_uppercase(pattern)
+    } else {
+        false
+    };
+
     let mut pattern = pattern.to_string();
-    
+
     if config.fixed_strings {
         pattern = regex::escape(&pattern);
     } else {
@@ -22,10 +34,93 @@ pub fn build_regex(pattern: &str, config: &RegexConfig) -> Result<Regex, regex::
     }
     
     RegexBuilder::new(&pattern)
-        .case_insensitive(config.ignore_case)
+        .case_insensitive(ignore_case)
         .build()
 }
 
+/// Builds the `Matcher` the search loop actually uses, choosing between the default `regex`
+/// engine and, when `-P/--pcre2` is set, the PCRE2 engine (feature-gated since it pulls in a
+/// C dependency). PCRE2 bypasses the basic/extended-regex escaping `build_regex` applies,
+/// since its syntax already matches what users expect from backreferences and lookaround.
+pub fn build_matcher(pattern: &str, config: &RegexConfig) -> Result<Matcher, String> {
+    if config.pcre2 {
+        #[cfg(feature = "pcre2")]
+        {
+            let ignore_case = if config.ignore_case {
+                true
+            } else {
+                config.smart_case && !pattern_has_uppercase(pattern)
+            };
+            return Pcre2RegexBuilder::new()
+                .caseless(ignore_case)
+                .build(pattern)
+                .map(Matcher::Pcre2)
+                .map_err(|e| e.to_string());
+        }
+        #[cfg(not(feature = "pcre2"))]
+        {
+            return Err("greprs was built without the `pcre2` feature".to_string());
+        }
+    }
+
+    build_regex(pattern, config)
+        .map(Matcher::Regex)
+        .map_err(|e| e.to_string())
+}
+
+/// Scans a pattern's literal text for an uppercase letter, used to decide whether
+/// `--smart-case` should enable case-insensitive matching. Characters that are part of
+/// regex metasyntax rather than literal text are skipped: a char immediately following an
+/// unescaped backslash (`\B`, `\W`, ...) doesn't count, since it's an escape code, not a
+/// literal uppercase letter.
+fn pattern_has_uppercase(pattern: &str) -> bool {
+    let mut escaped = false;
+    for c in pattern.chars() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        if c == '\\' {
+            escaped = true;
+            continue;
+        }
+        if c.is_uppercase() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Combines multiple pattern alternatives (from repeated `-e` and/or `-f`) into a single
+/// pattern implementing "matches any of them", mirroring GNU grep's `-e`/`-f` semantics. Each
+/// alternative's own literal text is escaped individually (the same way a lone pattern would
+/// be) before being joined with a real, unescaped `|`: escaping the combined string as a whole
+/// afterward, as `build_regex` does for a single pattern, would also escape the alternation
+/// syntax introduced here, turning it into a literal match instead of an OR. Callers pass the
+/// pre-combined result to `build_regex`/`build_matcher` with `extended_regexp: true` (and
+/// `fixed_strings: false`) so it isn't escaped a second time.
+pub fn combine_alternatives(alternatives: &[String], fixed_strings: bool, pcre2: bool) -> String {
+    if alternatives.len() == 1 {
+        return alternatives[0].clone();
+    }
+    alternatives
+        .iter()
+        .map(|p| {
+            let escaped = if fixed_strings {
+                regex::escape(p)
+            } else if pcre2 {
+                // PCRE2 patterns bypass `build_regex`'s BRE escaping entirely, so the raw
+                // pattern text is already what the user intended as regex syntax.
+                p.clone()
+            } else {
+                escape_basic_regex(p)
+            };
+            format!("(?:{})", escaped)
+        })
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
 fn escape_basic_regex(pattern: &str) -> String {
     let special_chars = ['+', '?', '|', '(', ')', '{', '}'];
     let mut result = String::with_capacity(pattern.len() * 2);
@@ -46,6 +141,8 @@ pub struct RegexConfig {
     pub line_regexp: bool,
     pub fixed_strings: bool,
     pub extended_regexp: bool,
+    pub smart_case: bool,
+    pub pcre2: bool,
 }
 
 impl Default for RegexConfig {
@@ -56,6 +153,8 @@ impl Default for RegexConfig {
             line_regexp: false,
             fixed_strings: false,
             extended_regexp: false,  // Basic regex is the default, like grep
+            smart_case: false,
+            pcre2: false,
         }
     }
 }
@@ -1,13 +1,21 @@
 #[cfg(test)]
 mod tests {
     use greprs::{
-        utils::{build_regex, RegexConfig},
-        search::{SearchConfig, visit_path}
+        utils::{build_regex, combine_alternatives, RegexConfig},
+        search::{BinaryDetection, SearchConfig, visit_path},
+        matcher::Matcher,
+        globset::GlobSet,
+        preprocess::{run as preprocess_run, PreprocessorConfig},
+        filetypes::TypeRegistry,
+        stats::Stats,
+        json::Text,
     };
+    #[cfg(feature = "pcre2")]
+    use greprs::utils::build_matcher;
     use std::fs::{self, File};
     use std::io::{self, Write};
+    use std::sync::Arc;
     use tempfile;
-    use glob::Pattern as GlobPattern;
 
     // First, let's fix the simple regex tests
     #[test]
@@ -32,6 +40,38 @@ mod tests {
         assert!(re.is_match("HELLO world"));
     }
 
+    #[test]
+    fn test_smart_case() {
+        let config = RegexConfig {
+            smart_case: true,
+            ..RegexConfig::default()
+        };
+
+        // All-lowercase pattern: matches case-insensitively
+        let re = build_regex("hello", &config).unwrap();
+        assert!(re.is_match("Hello world"));
+        assert!(re.is_match("HELLO world"));
+
+        // Mixed-case pattern: matches case-sensitively only
+        let re = build_regex("Hello", &config).unwrap();
+        assert!(re.is_match("Hello world"));
+        assert!(!re.is_match("hello world"));
+    }
+
+    #[test]
+    #[cfg(feature = "pcre2")]
+    fn test_pcre2_backreference() {
+        // `(\w+) \1` (a word, a space, then the same word again) needs a backreference, which
+        // the default `regex` engine can't express but PCRE2 can.
+        let config = RegexConfig {
+            pcre2: true,
+            ..RegexConfig::default()
+        };
+        let matcher = build_matcher(r"(\w+) \1", &config).unwrap();
+        assert!(matcher.is_match("hello hello world"));
+        assert!(!matcher.is_match("hello world"));
+    }
+
     #[test]
     fn test_invalid_regex() {
         let config = RegexConfig::default();
@@ -81,6 +121,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_multi_pattern_alternation_survives_escaping() {
+        // Mirrors how main.rs combines repeated -e/-f patterns: combine first, then build the
+        // regex with the combined-alternative escaping already applied (extended_regexp: true
+        // so build_regex doesn't escape the real `|`/`(?:...)` a second time).
+        let combined = combine_alternatives(
+            &["foo+".to_string(), "bar".to_string()],
+            /* fixed_strings */ false,
+            /* pcre2 */ false,
+        );
+        let config = RegexConfig {
+            extended_regexp: true,
+            ..RegexConfig::default()
+        };
+        let re = build_regex(&combined, &config).unwrap();
+
+        // Each alternative keeps its own basic-regex escaping: `+` is literal, not "one or
+        // more", so "foo+" matches only the literal text, while still participating in the OR.
+        assert!(re.is_match("a foo+ line"));
+        assert!(re.is_match("a bar line"));
+        assert!(!re.is_match("fooooo"));
+        assert!(!re.is_match("neither here"));
+    }
+
     #[test]
     fn test_basic_file_searching() -> std::io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -98,6 +162,7 @@ mod tests {
         };
         
         let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
         let mut output = Vec::new();
         visit_path(&re, &test_file, &config, false, &mut output)?;
         
@@ -128,6 +193,7 @@ mod tests {
         };
         
         let re = build_regex("MATCH", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
         let mut output = Vec::new();
         visit_path(&re, &test_file, &config, false, &mut output)?;
         
@@ -168,6 +234,7 @@ mod tests {
         };
         
         let re = build_regex("ERROR", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
         let mut output = Vec::new();
         visit_path(&re, &test_file, &config, false, &mut output)?;
         
@@ -198,6 +265,7 @@ mod tests {
         };
         
         let re = build_regex("Match", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
         let mut output = Vec::new();
         visit_path(&re, &test_file, &config, false, &mut output)?;
         
@@ -226,6 +294,7 @@ mod tests {
         };
         
         let re = build_regex("MATCH", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
         let mut output = Vec::new();
         visit_path(&re, &test_file, &config, false, &mut output)?;
         
@@ -236,6 +305,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_globset_literal_is_exact_match() {
+        let set = GlobSet::build(&["Makefile".to_string()]);
+        assert!(!set.matches("Makefile").is_empty());
+        // A substring match shouldn't count: the old glob::Pattern required a full match.
+        assert!(set.matches("Makefile.bak").is_empty());
+        assert!(set.matches("GNUMakefile").is_empty());
+    }
+
+    #[test]
+    fn test_globset_multi_dot_extension() {
+        let set = GlobSet::build(&["*.tar.gz".to_string()]);
+        assert!(!set.matches("archive.tar.gz").is_empty());
+        // `Path::extension()` only returns "gz", so this must not be routed through the
+        // extension fast path, which would make it unmatchable.
+        assert!(set.matches("archive.gz").is_empty());
+        assert!(set.matches("notes.txt").is_empty());
+    }
+
     #[test]
     fn test_directory_recursion() -> std::io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -259,6 +347,7 @@ mod tests {
         };
         
         let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
         let mut output = Vec::new();
         
         // Test non-recursive (should only find file1.txt)
@@ -295,12 +384,13 @@ mod tests {
         
         // Test include pattern - only .rs files
         let config = SearchConfig {
-            include_patterns: vec![GlobPattern::new("*.rs").unwrap()],
+            include_globs: GlobSet::build(&["*.rs".to_string()]),
             files_with_matches: true,
             ..SearchConfig::default()
         };
         
         let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
         let mut output = Vec::new();
         visit_path(&re, dir.path(), &config, true, &mut output)?;
         
@@ -311,7 +401,7 @@ mod tests {
         
         // Test exclude pattern - exclude .log files
         let config = SearchConfig {
-            exclude_patterns: vec![GlobPattern::new("*.log").unwrap()],
+            exclude_globs: GlobSet::build(&["*.log".to_string()]),
             files_with_matches: true,
             ..SearchConfig::default()
         };
@@ -327,6 +417,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_type_filters() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let files = vec![
+            ("main.rs", "Hello Rust"),
+            ("script.py", "Hello Python"),
+            ("notes.txt", "Hello Text"),
+        ];
+        for (filename, content) in &files {
+            let mut file = File::create(dir.path().join(filename))?;
+            writeln!(file, "{}", content)?;
+        }
+
+        let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
+
+        // --type rust: only main.rs
+        let config = SearchConfig {
+            files_with_matches: true,
+            type_filters: vec![(GlobSet::build(&["*.rs".to_string()]), true)],
+            ..SearchConfig::default()
+        };
+        let mut output = Vec::new();
+        visit_path(&re, dir.path(), &config, true, &mut output)?;
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(output_str.contains("main.rs"));
+        assert!(!output_str.contains("script.py"));
+        assert!(!output_str.contains("notes.txt"));
+
+        // --type-not rust: everything except main.rs
+        let config = SearchConfig {
+            files_with_matches: true,
+            type_filters: vec![(GlobSet::build(&["*.rs".to_string()]), false)],
+            ..SearchConfig::default()
+        };
+        output.clear();
+        visit_path(&re, dir.path(), &config, true, &mut output)?;
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(!output_str.contains("main.rs"));
+        assert!(output_str.contains("script.py"));
+        assert!(output_str.contains("notes.txt"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_special_options() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -344,6 +480,7 @@ mod tests {
         };
         
         let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
         let mut output = Vec::new();
         visit_path(&re, &test_file, &config, false, &mut output)?;
         
@@ -382,6 +519,7 @@ mod tests {
         };
         
         let re_nomatch = build_regex("NOMATCH", &RegexConfig::default()).unwrap();
+        let re_nomatch: Matcher = re_nomatch.into();
         output.clear();
         visit_path(&re_nomatch, &test_file, &config, false, &mut output)?;
         
@@ -406,6 +544,7 @@ mod tests {
         };
         
         let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
         let mut output = Vec::new();
         visit_path(&re, &test_file, &config, false, &mut output)?;
         
@@ -415,6 +554,371 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_gitignore_respected() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let mut gitignore = File::create(dir.path().join(".gitignore"))?;
+        writeln!(gitignore, "ignored.txt")?;
+
+        let mut kept = File::create(dir.path().join("kept.txt"))?;
+        writeln!(kept, "Hello World")?;
+
+        let mut ignored = File::create(dir.path().join("ignored.txt"))?;
+        writeln!(ignored, "Hello World")?;
+
+        let config = SearchConfig {
+            files_with_matches: true,
+            ..SearchConfig::default()
+        };
+
+        let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
+
+        // By default, the file listed in .gitignore is skipped
+        let mut output = Vec::new();
+        visit_path(&re, dir.path(), &config, true, &mut output)?;
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(output_str.contains("kept.txt"));
+        assert!(!output_str.contains("ignored.txt"));
+
+        // --no-ignore disables .gitignore handling
+        let config = SearchConfig {
+            files_with_matches: true,
+            no_ignore: true,
+            ..SearchConfig::default()
+        };
+        output.clear();
+        visit_path(&re, dir.path(), &config, true, &mut output)?;
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(output_str.contains("kept.txt"));
+        assert!(output_str.contains("ignored.txt"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gitignore_interior_slash_is_anchored() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let mut gitignore = File::create(dir.path().join(".gitignore"))?;
+        writeln!(gitignore, "src/foo.txt")?;
+
+        let sub_dir = dir.path().join("src");
+        fs::create_dir(&sub_dir)?;
+        let mut anchored_match = File::create(sub_dir.join("foo.txt"))?;
+        writeln!(anchored_match, "Hello World")?;
+
+        // A same-named file one level deeper must NOT be ignored: "src/foo.txt" has an
+        // interior slash, so it's anchored to the .gitignore's own directory, not every depth.
+        let nested_dir = sub_dir.join("nested");
+        fs::create_dir(&nested_dir)?;
+        let mut deeper_match = File::create(nested_dir.join("foo.txt"))?;
+        writeln!(deeper_match, "Hello World")?;
+
+        let config = SearchConfig {
+            files_with_matches: true,
+            ..SearchConfig::default()
+        };
+        let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
+
+        let mut output = Vec::new();
+        visit_path(&re, dir.path(), &config, true, &mut output)?;
+        let output_str = String::from_utf8_lossy(&output);
+
+        assert!(!output_str.contains(sub_dir.join("foo.txt").to_string_lossy().as_ref()));
+        assert!(output_str.contains(nested_dir.join("foo.txt").to_string_lossy().as_ref()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_output() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let test_file = dir.path().join("test.txt");
+        let mut file = File::create(&test_file)?;
+
+        writeln!(file, "no match")?;
+        writeln!(file, "has ERROR in it")?;
+
+        let config = SearchConfig {
+            json: true,
+            line_number: true,
+            ..SearchConfig::default()
+        };
+
+        let re = build_regex("ERROR", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
+        let mut output = Vec::new();
+        visit_path(&re, &test_file, &config, false, &mut output)?;
+
+        let output_str = String::from_utf8_lossy(&output);
+        let events: Vec<&str> = output_str.lines().collect();
+
+        // begin, match, end - one line each, in order
+        assert_eq!(events.len(), 3);
+        assert!(events[0].contains(r#""type":"begin""#));
+        assert!(events[1].contains(r#""type":"match""#));
+        assert!(events[1].contains("\"line_number\":2"));
+        assert!(events[1].contains("ERROR"));
+        assert!(events[2].contains(r#""type":"end""#));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_multiple_submatches() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let test_file = dir.path().join("test.txt");
+        let mut file = File::create(&test_file)?;
+
+        writeln!(file, "ERROR and another ERROR on one line")?;
+
+        let config = SearchConfig {
+            json: true,
+            ..SearchConfig::default()
+        };
+
+        let re = build_regex("ERROR", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
+        let mut output = Vec::new();
+        visit_path(&re, &test_file, &config, false, &mut output)?;
+
+        let output_str = String::from_utf8_lossy(&output);
+        let match_line = output_str
+            .lines()
+            .find(|l| l.contains(r#""type":"match""#))
+            .expect("a match event");
+
+        // Both occurrences of ERROR on the line are reported as separate submatches.
+        assert_eq!(match_line.matches(r#""match":{"text""#).count(), 2);
+        assert!(match_line.contains(r#""start":0"#));
+        assert!(match_line.contains(r#""start":18"#));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_text_base64_fallback_for_non_utf8() {
+        // Every text-bearing JSON field goes through `Text::from_bytes`: valid UTF-8 stays as
+        // plain text, anything else is base64-encoded so the output format never breaks.
+        let valid = Text::from_bytes("hello".as_bytes());
+        assert_eq!(serde_json::to_string(&valid).unwrap(), r#"{"text":"hello"}"#);
+
+        let invalid = Text::from_bytes(&[0xFF, 0xFE, 0xFD]);
+        assert_eq!(serde_json::to_string(&invalid).unwrap(), r#"{"bytes":"//79"}"#);
+    }
+
+    #[test]
+    fn test_search_zip() -> io::Result<()> {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let dir = tempfile::tempdir()?;
+        let gz_path = dir.path().join("test.txt.gz");
+
+        let mut encoder = GzEncoder::new(File::create(&gz_path)?, Compression::default());
+        writeln!(encoder, "Hello World")?;
+        encoder.finish()?;
+
+        let config = SearchConfig {
+            search_compressed: true,
+            files_with_matches: true,
+            ..SearchConfig::default()
+        };
+
+        let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
+        let mut output = Vec::new();
+        visit_path(&re, &gz_path, &config, false, &mut output)?;
+
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(output_str.contains("test.txt.gz"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_counts_matches_and_respects_max_count() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let test_file = dir.path().join("test.txt");
+        let mut file = File::create(&test_file)?;
+        for i in 1..=5 {
+            writeln!(file, "Match line {}", i)?;
+        }
+
+        let re = build_regex("Match", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
+
+        // Without --max-count, all 5 matching lines are counted.
+        let stats = Arc::new(Stats::default());
+        let config = SearchConfig {
+            stats: Some(stats.clone()),
+            ..SearchConfig::default()
+        };
+        let mut output = Vec::new();
+        visit_path(&re, &test_file, &config, false, &mut output)?;
+        let mut summary = Vec::new();
+        stats.print_summary(&mut summary, std::time::Duration::from_secs(0))?;
+        let summary_str = String::from_utf8_lossy(&summary);
+        assert!(summary_str.contains("5 matches"));
+        assert!(summary_str.contains("5 matched lines"));
+        assert!(summary_str.contains("1 files contained matches"));
+
+        // With --max-count 2, stats reflect only the 2 lines actually reported.
+        let stats = Arc::new(Stats::default());
+        let config = SearchConfig {
+            stats: Some(stats.clone()),
+            max_count: Some(2),
+            ..SearchConfig::default()
+        };
+        output.clear();
+        visit_path(&re, &test_file, &config, false, &mut output)?;
+        let mut summary = Vec::new();
+        stats.print_summary(&mut summary, std::time::Duration::from_secs(0))?;
+        let summary_str = String::from_utf8_lossy(&summary);
+        assert!(summary_str.contains("2 matches"));
+        assert!(summary_str.contains("2 matched lines"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_type_registry_builtin_and_custom() {
+        let mut registry = TypeRegistry::with_builtins();
+
+        let rust_globs = registry.globs_for("rust").expect("builtin 'rust' type");
+        assert!(rust_globs.iter().any(|g| g == "*.rs"));
+
+        assert!(registry.globs_for("frobnicate").is_none());
+
+        registry.add("frobnicate:*.frob").unwrap();
+        let custom_globs = registry.globs_for("frobnicate").expect("custom type was added");
+        assert_eq!(custom_globs, &["*.frob".to_string()]);
+
+        assert!(registry.add("missing-colon").is_err());
+    }
+
+    #[test]
+    fn test_binary_detection_by_content() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        // Deliberately named .txt: detection must be content-based, not extension-based.
+        let test_file = dir.path().join("looks_like_text.txt");
+        fs::write(&test_file, b"has ERROR\0and a NUL byte")?;
+
+        let re = build_regex("ERROR", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
+
+        // Default (Quit): prints the binary-file notice instead of the match.
+        let config = SearchConfig::default();
+        let mut output = Vec::new();
+        visit_path(&re, &test_file, &config, false, &mut output)?;
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(output_str.to_lowercase().contains("binary file"));
+        assert!(!output_str.contains("has ERROR"));
+
+        // Skip: no notice and no match.
+        let config = SearchConfig {
+            binary_detection: BinaryDetection::Skip,
+            ..SearchConfig::default()
+        };
+        output.clear();
+        visit_path(&re, &test_file, &config, false, &mut output)?;
+        assert!(output.is_empty());
+
+        // Disabled (-a/--text): searches straight through the NUL byte.
+        let config = SearchConfig {
+            binary_detection: BinaryDetection::Disabled,
+            ..SearchConfig::default()
+        };
+        output.clear();
+        visit_path(&re, &test_file, &config, false, &mut output)?;
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(output_str.contains("has ERROR"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pre_preprocessor() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let test_file = dir.path().join("test.data");
+        let mut file = File::create(&test_file)?;
+        writeln!(file, "Hello World")?;
+
+        // An explicit --pre command pipes the file through it and searches its stdout.
+        let config = PreprocessorConfig {
+            command: Some("cat".into()),
+            ..PreprocessorConfig::default()
+        };
+        let contents = preprocess_run(&test_file, &config)?;
+        assert_eq!(contents.as_deref(), Some("Hello World\n"));
+
+        // Without --pre and without --pre-builtins, nothing runs, even for an extension a
+        // built-in adapter would otherwise handle.
+        let pdf_file = dir.path().join("test.pdf");
+        File::create(&pdf_file)?;
+        let config = PreprocessorConfig::default();
+        assert!(preprocess_run(&pdf_file, &config)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_zip_bzip2() -> io::Result<()> {
+        use bzip2::write::BzEncoder;
+        use bzip2::Compression;
+
+        let dir = tempfile::tempdir()?;
+        let bz2_path = dir.path().join("test.txt.bz2");
+
+        let mut encoder = BzEncoder::new(File::create(&bz2_path)?, Compression::default());
+        writeln!(encoder, "Hello World")?;
+        encoder.finish()?;
+
+        let config = SearchConfig {
+            search_compressed: true,
+            files_with_matches: true,
+            ..SearchConfig::default()
+        };
+
+        let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
+        let mut output = Vec::new();
+        visit_path(&re, &bz2_path, &config, false, &mut output)?;
+
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(output_str.contains("test.txt.bz2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_utf16_bom_auto_detected() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let test_file = dir.path().join("test.txt");
+
+        // UTF-16LE BOM followed by "Hello World\n"; every other byte is NUL, which would look
+        // binary if scanned before transcoding.
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in "Hello World\n".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        fs::write(&test_file, &bytes)?;
+
+        let config = SearchConfig::default();
+        let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
+        let mut output = Vec::new();
+        visit_path(&re, &test_file, &config, false, &mut output)?;
+
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(output_str.contains("Hello World"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_null_separators() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -431,6 +935,7 @@ mod tests {
         };
         
         let re = build_regex("Hello", &RegexConfig::default()).unwrap();
+        let re: Matcher = re.into();
         let mut output = Vec::new();
         visit_path(&re, &test_file, &config, false, &mut output)?;
         